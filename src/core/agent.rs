@@ -0,0 +1,48 @@
+use async_openai::{
+    config::OpenAIConfig,
+    types::{
+        ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+        CreateChatCompletionRequestArgs,
+    },
+    Client,
+};
+
+pub struct Agent {
+    client: Client<OpenAIConfig>,
+    system_prompt: String,
+}
+
+impl Agent {
+    pub fn new(openai_api_key: &str, system_prompt: &str) -> Self {
+        let config = OpenAIConfig::new().with_api_key(openai_api_key);
+        Agent {
+            client: Client::with_config(config),
+            system_prompt: system_prompt.to_string(),
+        }
+    }
+
+    pub async fn prompt(&self, input: &str) -> Result<String, anyhow::Error> {
+        let request = CreateChatCompletionRequestArgs::default()
+            .model("gpt-4o")
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(self.system_prompt.clone())
+                    .build()?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(input.to_string())
+                    .build()?
+                    .into(),
+            ])
+            .build()?;
+
+        let response = self.client.chat().create(request).await?;
+        let content = response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .ok_or_else(|| anyhow::anyhow!("No response from model"))?;
+
+        Ok(content)
+    }
+}
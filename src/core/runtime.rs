@@ -1,129 +1,524 @@
-use rand::Rng;
-use tokio::time::{sleep, Duration};
-
-use crate::{
-    core::agent::Agent,
-    memory::MemoryStore,
-    providers::{ai16z_twitter::Ai16zTwitter, discord::Discord, twitter::Twitter},
-};
-
-pub enum TwitterType {
-    ApiKeys(Twitter),
-    Ai16zTwitter(Ai16zTwitter),
-}
-
-impl TwitterType {
-    pub async fn tweet(&self, text: &str) -> Result<(), anyhow::Error> {
-        match self {
-            TwitterType::ApiKeys(twitter) => {
-                // Call the tweet method for Twitter API
-                twitter.tweet(text.to_string()).await
-            }
-            TwitterType::Ai16zTwitter(ai6z_twitter) => {
-                // Call the tweet method for Ai6zTwitter
-                ai6z_twitter.tweet(text.to_string()).await
-            }
-        }
-    }
-}
-
-pub struct Runtime {
-    openai_api_key: String,
-    twitter: TwitterType,
-    discord: Discord,
-    agents: Vec<Agent>,
-    memory: Vec<String>,
-}
-
-impl Runtime {
-    pub fn new(
-        openai_api_key: &str,
-        discord_webhook_url: &str,
-        twitter_consumer_key: Option<&str>,
-        twitter_consumer_secret: Option<&str>,
-        twitter_access_token: Option<&str>,
-        twitter_access_token_secret: Option<&str>,
-        twitter_username: Option<&str>,
-        twitter_password: Option<&str>,
-    ) -> Self {
-        let twitter = match (twitter_username, twitter_password) {
-            (Some(username), Some(password)) => {
-                // If both username and password are provided, prioritize Ai6zTwitter
-                TwitterType::Ai16zTwitter(Ai16zTwitter::new(username, password))
-            }
-            (_, _) => {
-                // Otherwise, fall back to Twitter API keys if available
-                match (
-                    twitter_consumer_key,
-                    twitter_consumer_secret,
-                    twitter_access_token,
-                    twitter_access_token_secret,
-                ) {
-                    (
-                        Some(consumer_key),
-                        Some(consumer_secret),
-                        Some(access_token),
-                        Some(access_token_secret),
-                    ) => TwitterType::ApiKeys(Twitter::new(
-                        consumer_key,
-                        consumer_secret,
-                        access_token,
-                        access_token_secret,
-                    )),
-                    _ => panic!("You must provide either Twitter username/password or API keys."),
-                }
-            }
-        };
-        let discord = Discord::new(discord_webhook_url);
-
-        let agents = Vec::new();
-        let memory: Vec<String> = MemoryStore::load_memory().unwrap_or_else(|_| Vec::new());
-
-        Runtime {
-            discord,
-            memory,
-            openai_api_key: openai_api_key.to_string(),
-            agents,
-            twitter,
-        }
-    }
-
-    pub fn add_agent(&mut self, prompt: &str) {
-        let agent = Agent::new(&self.openai_api_key, prompt);
-        self.agents.push(agent);
-    }
-
-    pub async fn run(&mut self) -> Result<(), anyhow::Error> {
-        if self.agents.is_empty() {
-            return Err(anyhow::anyhow!("No agents available")).map_err(Into::into);
-        }
-
-        let mut rng = rand::thread_rng();
-        let selected_agent = &self.agents[rng.gen_range(0..self.agents.len())];
-        let response = selected_agent.prompt("tweet").await?;
-
-        match MemoryStore::add_to_memory(&mut self.memory, &response) {
-            Ok(_) => println!("Response saved to memory."),
-            Err(e) => eprintln!("Failed to save response to memory: {}", e),
-        }
-
-        println!("AI Response: {}", response);
-        self.discord.send_channel_message(&response.clone()).await;
-        self.twitter.tweet(&response).await?;
-        Ok(())
-    }
-
-    pub async fn run_periodically(&mut self) -> Result<(), anyhow::Error> {
-        let mut rng = rand::thread_rng();
-
-        loop {
-            let random_sleep_duration = rng.gen_range(300..=1800);
-
-            sleep(Duration::from_secs(random_sleep_duration)).await;
-
-            if let Err(e) = self.run().await {
-                eprintln!("Error running process: {}", e);
-            }
-        }
-    }
-}
+use rand::Rng;
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    core::agent::Agent,
+    memory::MemoryStore,
+    providers::{
+        ai16z_twitter::Ai16zTwitter, discord::Discord, splitter::split_into_tweets,
+        twitter::Twitter, Mention,
+    },
+};
+
+/// Starting delay for the mentions-poll reconnect backoff.
+const RECONNECT_BACKOFF_FLOOR_SECS: u64 = 1;
+/// Default ceiling the backoff doubles up to before holding steady; tunable
+/// via `Runtime::configure_reconnect_policy`.
+const DEFAULT_RECONNECT_BACKOFF_CEILING_SECS: u64 = 120;
+/// Default number of consecutive transient failures tolerated before giving up.
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+pub enum TwitterType {
+    ApiKeys(Twitter),
+    Ai16zTwitter(Ai16zTwitter),
+}
+
+impl TwitterType {
+    /// Posts `text` as a single status, returning the id of the created tweet.
+    pub async fn tweet(&self, text: &str) -> Result<String, anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => {
+                // Call the tweet method for Twitter API
+                twitter.tweet(text.to_string()).await
+            }
+            TwitterType::Ai16zTwitter(ai6z_twitter) => {
+                // Call the tweet method for Ai6zTwitter
+                ai6z_twitter.tweet(text.to_string()).await
+            }
+        }
+    }
+
+    pub async fn tweet_reply(
+        &self,
+        text: &str,
+        in_reply_to_id: &str,
+    ) -> Result<String, anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.tweet_reply(text, in_reply_to_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => {
+                ai6z_twitter.tweet_reply(text, in_reply_to_id).await
+            }
+        }
+    }
+
+    pub async fn fetch_mentions(
+        &self,
+        since_id: Option<&str>,
+    ) -> Result<Vec<Mention>, anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.fetch_mentions(since_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => ai6z_twitter.fetch_mentions(since_id).await,
+        }
+    }
+
+    pub async fn favorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.favorite(tweet_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => ai6z_twitter.favorite(tweet_id).await,
+        }
+    }
+
+    pub async fn unfavorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.unfavorite(tweet_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => ai6z_twitter.unfavorite(tweet_id).await,
+        }
+    }
+
+    pub async fn follow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.follow(user_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => ai6z_twitter.follow(user_id).await,
+        }
+    }
+
+    pub async fn unfollow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        match self {
+            TwitterType::ApiKeys(twitter) => twitter.unfollow(user_id).await,
+            TwitterType::Ai16zTwitter(ai6z_twitter) => ai6z_twitter.unfollow(user_id).await,
+        }
+    }
+
+    /// Posts `text` as a thread when it's too long for a single tweet, chaining
+    /// each chunk onto the previous one via `in_reply_to_status_id`. Returns the
+    /// id of the first tweet in the thread.
+    pub async fn tweet_thread(&self, text: &str) -> Result<String, anyhow::Error> {
+        let chunks = split_into_tweets(text);
+        let mut chunks = chunks.into_iter();
+
+        let first_chunk = chunks
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("tweet_thread called with empty text"))?;
+        let first_id = self.tweet(&first_chunk).await?;
+
+        let mut previous_id = first_id.clone();
+        for chunk in chunks {
+            previous_id = self.tweet_reply(&chunk, &previous_id).await?;
+        }
+
+        Ok(first_id)
+    }
+}
+
+/// A structured action an `Agent` can emit instead of plain tweet text, e.g.
+/// `{"action":"fav","target":"<tweet_id>"}`, letting it engage with accounts
+/// rather than only post.
+#[derive(serde::Deserialize)]
+struct AgentAction {
+    action: String,
+    target: String,
+}
+
+pub struct Runtime {
+    openai_api_key: String,
+    twitter: TwitterType,
+    twitter_handle: String,
+    discord: Discord,
+    agents: Vec<Agent>,
+    memory: Vec<String>,
+    actions: Vec<String>,
+    since_id: Option<String>,
+    reconnect_backoff_ceiling_secs: u64,
+    max_reconnect_attempts: u32,
+}
+
+impl Runtime {
+    pub fn new(
+        openai_api_key: &str,
+        discord_webhook_url: &str,
+        twitter_handle: &str,
+        twitter_consumer_key: Option<&str>,
+        twitter_consumer_secret: Option<&str>,
+        twitter_access_token: Option<&str>,
+        twitter_access_token_secret: Option<&str>,
+        twitter_username: Option<&str>,
+        twitter_password: Option<&str>,
+    ) -> Self {
+        let twitter = match (twitter_username, twitter_password) {
+            (Some(username), Some(password)) => {
+                // If both username and password are provided, prioritize Ai6zTwitter
+                TwitterType::Ai16zTwitter(Ai16zTwitter::new(username, password))
+            }
+            (_, _) => {
+                // Otherwise, fall back to Twitter API keys if available
+                match (
+                    twitter_consumer_key,
+                    twitter_consumer_secret,
+                    twitter_access_token,
+                    twitter_access_token_secret,
+                ) {
+                    (
+                        Some(consumer_key),
+                        Some(consumer_secret),
+                        Some(access_token),
+                        Some(access_token_secret),
+                    ) => TwitterType::ApiKeys(Twitter::new(
+                        consumer_key,
+                        consumer_secret,
+                        access_token,
+                        access_token_secret,
+                    )),
+                    _ => panic!("You must provide either Twitter username/password or API keys."),
+                }
+            }
+        };
+        let discord = Discord::new(discord_webhook_url);
+
+        let agents = Vec::new();
+        let memory: Vec<String> = MemoryStore::load_memory().unwrap_or_else(|_| Vec::new());
+        let actions: Vec<String> = MemoryStore::load_actions().unwrap_or_else(|_| Vec::new());
+        let since_id = MemoryStore::load_since_id();
+
+        Runtime {
+            discord,
+            memory,
+            actions,
+            openai_api_key: openai_api_key.to_string(),
+            agents,
+            twitter,
+            twitter_handle: twitter_handle.to_string(),
+            since_id,
+            reconnect_backoff_ceiling_secs: DEFAULT_RECONNECT_BACKOFF_CEILING_SECS,
+            max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS,
+        }
+    }
+
+    /// Builds a `Runtime` backed by `TwitterType::ApiKeys`, obtaining access
+    /// tokens via the interactive PIN flow (see `Twitter::from_pin_auth`)
+    /// instead of requiring them to be pre-obtained from the developer portal.
+    pub async fn new_with_pin_auth(
+        openai_api_key: &str,
+        discord_webhook_url: &str,
+        twitter_handle: &str,
+        twitter_consumer_key: &str,
+        twitter_consumer_secret: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let twitter = Twitter::from_pin_auth(twitter_consumer_key, twitter_consumer_secret).await?;
+        let discord = Discord::new(discord_webhook_url);
+
+        let agents = Vec::new();
+        let memory: Vec<String> = MemoryStore::load_memory().unwrap_or_else(|_| Vec::new());
+        let actions: Vec<String> = MemoryStore::load_actions().unwrap_or_else(|_| Vec::new());
+        let since_id = MemoryStore::load_since_id();
+
+        Ok(Runtime {
+            discord,
+            memory,
+            actions,
+            openai_api_key: openai_api_key.to_string(),
+            agents,
+            twitter: TwitterType::ApiKeys(twitter),
+            twitter_handle: twitter_handle.to_string(),
+            since_id,
+            reconnect_backoff_ceiling_secs: DEFAULT_RECONNECT_BACKOFF_CEILING_SECS,
+            max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS,
+        })
+    }
+
+    pub fn add_agent(&mut self, prompt: &str) {
+        let agent = Agent::new(&self.openai_api_key, prompt);
+        self.agents.push(agent);
+    }
+
+    /// Tunes the reconnect policy used by `listen_for_mentions`: `ceiling_secs`
+    /// caps how high the exponential backoff can climb, and `max_attempts`
+    /// bounds how many consecutive transient failures are tolerated before
+    /// giving up and surfacing an error.
+    pub fn configure_reconnect_policy(&mut self, ceiling_secs: u64, max_attempts: u32) {
+        self.reconnect_backoff_ceiling_secs = ceiling_secs;
+        self.max_reconnect_attempts = max_attempts;
+    }
+
+    pub async fn run(&mut self) -> Result<(), anyhow::Error> {
+        if self.agents.is_empty() {
+            return Err(anyhow::anyhow!("No agents available")).map_err(Into::into);
+        }
+
+        let mut rng = rand::thread_rng();
+        let selected_agent = &self.agents[rng.gen_range(0..self.agents.len())];
+        let response = selected_agent.prompt("tweet").await?;
+
+        if let Ok(action) = serde_json::from_str::<AgentAction>(response.trim()) {
+            return self.dispatch_action(action).await;
+        }
+
+        match MemoryStore::add_to_memory(&mut self.memory, &response) {
+            Ok(_) => println!("Response saved to memory."),
+            Err(e) => eprintln!("Failed to save response to memory: {}", e),
+        }
+
+        println!("AI Response: {}", response);
+        self.discord.send_channel_message(&response.clone()).await;
+        self.twitter.tweet_thread(&response).await?;
+        Ok(())
+    }
+
+    /// Dispatches a structured action emitted by an agent (fav/unfav/follow/unfollow),
+    /// skipping it if we've already performed the exact same action on the same
+    /// target before, and recording it via `MemoryStore::record_action` once it
+    /// succeeds. Kept out of `self.memory` so this bookkeeping never shows up as
+    /// "recent context" in `reply_to_mention`'s prompts.
+    async fn dispatch_action(&mut self, action: AgentAction) -> Result<(), anyhow::Error> {
+        let record = format!("action:{}:{}", action.action, action.target);
+        if self.actions.contains(&record) {
+            println!(
+                "Already performed {} on {}, skipping.",
+                action.action, action.target
+            );
+            return Ok(());
+        }
+
+        match action.action.as_str() {
+            "fav" => self.twitter.favorite(&action.target).await?,
+            "unfav" => self.twitter.unfavorite(&action.target).await?,
+            "follow" => self.twitter.follow(&action.target).await?,
+            "unfollow" => self.twitter.unfollow(&action.target).await?,
+            other => return Err(anyhow::anyhow!("Unknown agent action: {}", other)),
+        }
+
+        MemoryStore::record_action(&mut self.actions, &record)?;
+        Ok(())
+    }
+
+    /// Polls the mentions timeline on an interval and has a random agent reply
+    /// in context, rather than only posting standalone tweets.
+    ///
+    /// `poll_interval_secs` controls how often the mentions endpoint is checked,
+    /// and `max_replies_per_interval` caps how many replies are sent per poll so
+    /// a burst of mentions can't trip Twitter's rate limits.
+    ///
+    /// A dropped or failing poll is treated as a stalled connection: transient
+    /// errors are retried with exponentially increasing backoff (reset to the
+    /// floor after the next successful poll), bounded by
+    /// `reconnect_backoff_ceiling_secs` and `max_reconnect_attempts`, while a
+    /// fatal auth error (401/403) stops the loop and surfaces immediately.
+    pub async fn listen_for_mentions(
+        &mut self,
+        poll_interval_secs: u64,
+        max_replies_per_interval: usize,
+    ) -> Result<(), anyhow::Error> {
+        let mut backoff_secs = RECONNECT_BACKOFF_FLOOR_SECS;
+        let mut attempt = 0;
+        // The delay before the *next* fetch attempt: the normal poll interval
+        // on a healthy connection, or the backoff delay while reconnecting.
+        // The backoff sleep substitutes for this, it never stacks on top of it.
+        let mut next_sleep_secs = poll_interval_secs;
+
+        loop {
+            sleep(Duration::from_secs(next_sleep_secs)).await;
+            next_sleep_secs = poll_interval_secs;
+
+            let mut mentions = match self.twitter.fetch_mentions(self.since_id.as_deref()).await {
+                Ok(mentions) => {
+                    backoff_secs = RECONNECT_BACKOFF_FLOOR_SECS;
+                    attempt = 0;
+                    mentions
+                }
+                Err(e) if is_fatal_auth_error(&e) => {
+                    eprintln!("Fatal auth error while polling mentions, stopping: {}", e);
+                    return Err(e);
+                }
+                Err(e) => {
+                    attempt += 1;
+                    if attempt > self.max_reconnect_attempts {
+                        return Err(anyhow::anyhow!(
+                            "Giving up after {} reconnect attempts: {}",
+                            self.max_reconnect_attempts,
+                            e
+                        ));
+                    }
+
+                    let jitter = rand::thread_rng().gen_range(0..=backoff_secs.max(1));
+                    let delay_secs = backoff_secs + jitter;
+                    eprintln!(
+                        "Mentions poll failed ({}), reconnecting in {}s (attempt {}/{}, backoff {}s)",
+                        e, delay_secs, attempt, self.max_reconnect_attempts, backoff_secs
+                    );
+                    next_sleep_secs = delay_secs;
+
+                    backoff_secs = (backoff_secs * 2).min(self.reconnect_backoff_ceiling_secs);
+                    continue;
+                }
+            };
+
+            // Only advance `since_id` past mentions we actually finish handling;
+            // anything left behind by the reply cap must still be fetched again
+            // on the next poll instead of being silently dropped. Mentions are
+            // handled oldest-first so everything the cap leaves behind has a
+            // higher id than the `since_id` saved below.
+            mentions.sort_by_key(|mention| mention.id.parse::<u64>().unwrap_or(0));
+            let mut highest_processed: Option<String> = None;
+            let mut replies_sent = 0;
+            for mention in mentions {
+                if mention
+                    .author_handle
+                    .eq_ignore_ascii_case(&self.twitter_handle)
+                {
+                    // Never reply to ourselves, or we'd loop forever.
+                    advance_since_id(&mut highest_processed, &mention.id);
+                    continue;
+                }
+
+                if replies_sent >= max_replies_per_interval {
+                    println!("Reached reply cap for this interval, leaving remaining mentions for the next poll.");
+                    break;
+                }
+
+                if self.agents.is_empty() {
+                    continue;
+                }
+
+                let response = match self.reply_to_mention(&mention).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        eprintln!("Failed to generate reply: {}", e);
+                        continue;
+                    }
+                };
+
+                if let Ok(action) = serde_json::from_str::<AgentAction>(response.trim()) {
+                    if let Err(e) = self.dispatch_action(action).await {
+                        eprintln!("Failed to dispatch agent action: {}", e);
+                    }
+                    advance_since_id(&mut highest_processed, &mention.id);
+                    continue;
+                }
+
+                match self.twitter.tweet_reply(&response, &mention.id).await {
+                    Ok(_) => {
+                        replies_sent += 1;
+                        advance_since_id(&mut highest_processed, &mention.id);
+                        if let Err(e) = MemoryStore::add_to_memory(&mut self.memory, &response) {
+                            eprintln!("Failed to save reply to memory: {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to post reply to {}: {}", mention.id, e),
+                }
+            }
+
+            if let Some(highest) = highest_processed {
+                self.since_id = Some(highest);
+                if let Err(e) = MemoryStore::save_since_id(self.since_id.as_ref().unwrap()) {
+                    eprintln!("Failed to persist since_id: {}", e);
+                }
+            }
+        }
+    }
+
+    async fn reply_to_mention(&self, mention: &Mention) -> Result<String, anyhow::Error> {
+        let mut rng = rand::thread_rng();
+        let selected_agent = &self.agents[rng.gen_range(0..self.agents.len())];
+
+        let recent_context = self
+            .memory
+            .iter()
+            .rev()
+            .take(5)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let prompt = format!(
+            "Reply to this tweet from @{}: \"{}\"\n\nRecent context:\n{}",
+            mention.author_handle, mention.text, recent_context
+        );
+
+        selected_agent.prompt(&prompt).await
+    }
+
+    pub async fn run_periodically(&mut self) -> Result<(), anyhow::Error> {
+        let mut rng = rand::thread_rng();
+
+        loop {
+            let random_sleep_duration = rng.gen_range(300..=1800);
+
+            sleep(Duration::from_secs(random_sleep_duration)).await;
+
+            if let Err(e) = self.run().await {
+                eprintln!("Error running process: {}", e);
+            }
+        }
+    }
+}
+
+/// Raises `current` to `candidate` if `candidate` is numerically higher, so the
+/// `since_id` high-water mark only ever advances past mentions we've actually
+/// finished handling, regardless of the order Twitter returns them in.
+fn advance_since_id(current: &mut Option<String>, candidate: &str) {
+    let Ok(candidate_value) = candidate.parse::<u64>() else {
+        return;
+    };
+
+    let should_replace = match current {
+        Some(existing) => existing
+            .parse::<u64>()
+            .map(|existing_value| candidate_value > existing_value)
+            .unwrap_or(true),
+        None => true,
+    };
+
+    if should_replace {
+        *current = Some(candidate.to_string());
+    }
+}
+
+/// Distinguishes a fatal auth failure (401/403, e.g. revoked or expired
+/// credentials) from a transient network error that's worth retrying.
+fn is_fatal_auth_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|e| e.status())
+        .map(|status| status.as_u16() == 401 || status.as_u16() == 403)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_since_id_sets_value_from_none() {
+        let mut current = None;
+        advance_since_id(&mut current, "100");
+        assert_eq!(current, Some("100".to_string()));
+    }
+
+    #[test]
+    fn advance_since_id_replaces_with_a_numerically_higher_candidate() {
+        let mut current = Some("100".to_string());
+        advance_since_id(&mut current, "250");
+        assert_eq!(current, Some("250".to_string()));
+    }
+
+    #[test]
+    fn advance_since_id_ignores_a_numerically_lower_or_equal_candidate() {
+        let mut current = Some("250".to_string());
+        advance_since_id(&mut current, "100");
+        assert_eq!(current, Some("250".to_string()));
+
+        advance_since_id(&mut current, "250");
+        assert_eq!(current, Some("250".to_string()));
+    }
+
+    #[test]
+    fn advance_since_id_ignores_a_non_numeric_candidate() {
+        let mut current = Some("250".to_string());
+        advance_since_id(&mut current, "not-a-number");
+        assert_eq!(current, Some("250".to_string()));
+    }
+
+    #[test]
+    fn is_fatal_auth_error_is_false_for_a_non_http_error() {
+        let error = anyhow::anyhow!("connection reset by peer");
+        assert!(!is_fatal_auth_error(&error));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs;
+
+const MEMORY_FILE: &str = "memory.json";
+const SINCE_ID_FILE: &str = "since_id.txt";
+const TWITTER_TOKENS_FILE: &str = "twitter_tokens.json";
+const ACTIONS_FILE: &str = "actions.json";
+
+pub struct MemoryStore;
+
+impl MemoryStore {
+    pub fn load_memory() -> Result<Vec<String>, anyhow::Error> {
+        let data = fs::read_to_string(MEMORY_FILE)?;
+        let memory: Vec<String> = serde_json::from_str(&data)?;
+        Ok(memory)
+    }
+
+    pub fn add_to_memory(memory: &mut Vec<String>, entry: &str) -> Result<(), anyhow::Error> {
+        memory.push(entry.to_string());
+        let data = serde_json::to_string_pretty(memory)?;
+        fs::write(MEMORY_FILE, data)?;
+        Ok(())
+    }
+
+    /// Loads the `since_id` high-water mark left over from a previous run, if any.
+    pub fn load_since_id() -> Option<String> {
+        fs::read_to_string(SINCE_ID_FILE)
+            .ok()
+            .map(|contents| contents.trim().to_string())
+            .filter(|contents| !contents.is_empty())
+    }
+
+    /// Persists the `since_id` high-water mark so a restart doesn't reprocess old mentions.
+    pub fn save_since_id(since_id: &str) -> Result<(), anyhow::Error> {
+        fs::write(SINCE_ID_FILE, since_id)?;
+        Ok(())
+    }
+
+    /// Loads a previously cached `(access_token, access_token_secret)` pair obtained
+    /// via the PIN authorization flow, if one was saved on an earlier run.
+    pub fn load_twitter_tokens() -> Option<(String, String)> {
+        let data = fs::read_to_string(TWITTER_TOKENS_FILE).ok()?;
+        let tokens: (String, String) = serde_json::from_str(&data).ok()?;
+        Some(tokens)
+    }
+
+    /// Caches the access token pair returned by the PIN authorization flow so
+    /// it doesn't need to be re-run on every restart.
+    pub fn save_twitter_tokens(
+        access_token: &str,
+        access_token_secret: &str,
+    ) -> Result<(), anyhow::Error> {
+        let data = serde_json::to_string_pretty(&(access_token, access_token_secret))?;
+        fs::write(TWITTER_TOKENS_FILE, data)?;
+        Ok(())
+    }
+
+    /// Loads previously recorded engagement actions (e.g. `"action:fav:172839"`), kept
+    /// separate from `load_memory` so idempotency bookkeeping never leaks into
+    /// the conversational context built from tweet content.
+    pub fn load_actions() -> Result<Vec<String>, anyhow::Error> {
+        let data = fs::read_to_string(ACTIONS_FILE)?;
+        let actions: Vec<String> = serde_json::from_str(&data)?;
+        Ok(actions)
+    }
+
+    /// Records that an engagement action was performed, so it isn't repeated.
+    pub fn record_action(actions: &mut Vec<String>, record: &str) -> Result<(), anyhow::Error> {
+        actions.push(record.to_string());
+        let data = serde_json::to_string_pretty(actions)?;
+        fs::write(ACTIONS_FILE, data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use reqwest::Client;
+use serde_json::json;
+
+use crate::providers::Mention;
+
+const LOGIN_URL: &str = "https://api.twitter.com/1.1/onboarding/task.json";
+const CREATE_TWEET_URL: &str =
+    "https://twitter.com/i/api/graphql/a1p9RWpkYKBjWv_I3WzS-A/CreateTweet";
+const MENTIONS_URL: &str = "https://twitter.com/i/api/2/notifications/mentions.json";
+const FAVORITE_TWEET_URL: &str =
+    "https://twitter.com/i/api/graphql/lI07N6Otwv1PVZZjtRDM5A/FavoriteTweet";
+const UNFAVORITE_TWEET_URL: &str =
+    "https://twitter.com/i/api/graphql/ZYKSe-w7KEslx3JhSIk5LA/UnfavoriteTweet";
+const FOLLOW_URL: &str = "https://api.twitter.com/1.1/friendships/create.json";
+const UNFOLLOW_URL: &str = "https://api.twitter.com/1.1/friendships/destroy.json";
+
+/// A scraping-based Twitter client that authenticates with a plain
+/// username/password login flow instead of developer-portal API keys.
+pub struct Ai16zTwitter {
+    username: String,
+    password: String,
+    client: Client,
+}
+
+impl Ai16zTwitter {
+    pub fn new(username: &str, password: &str) -> Self {
+        Ai16zTwitter {
+            username: username.to_string(),
+            password: password.to_string(),
+            client: Client::builder()
+                .cookie_store(true)
+                .build()
+                .expect("failed to build HTTP client"),
+        }
+    }
+
+    /// Posts `text` as a new status, returning the id of the created tweet.
+    pub async fn tweet(&self, text: String) -> Result<String, anyhow::Error> {
+        self.tweet_internal(&text, None).await
+    }
+
+    /// Posts `text` as a reply to `in_reply_to_id`, returning the id of the new tweet.
+    pub async fn tweet_reply(
+        &self,
+        text: &str,
+        in_reply_to_id: &str,
+    ) -> Result<String, anyhow::Error> {
+        self.tweet_internal(text, Some(in_reply_to_id)).await
+    }
+
+    /// Polls recent mentions, optionally bounded below by `since_id`.
+    pub async fn fetch_mentions(
+        &self,
+        since_id: Option<&str>,
+    ) -> Result<Vec<Mention>, anyhow::Error> {
+        self.ensure_logged_in().await?;
+
+        let mut request = self.client.get(MENTIONS_URL);
+        if let Some(since_id) = since_id {
+            request = request.query(&[("since_id", since_id)]);
+        }
+
+        let raw: serde_json::Value = request.send().await?.error_for_status()?.json().await?;
+
+        let mentions = raw
+            .get("globalObjects")
+            .and_then(|obj| obj.get("tweets"))
+            .and_then(|tweets| tweets.as_object())
+            .map(|tweets| {
+                tweets
+                    .values()
+                    .filter_map(|tweet| {
+                        let id = tweet.get("id_str")?.as_str()?.to_string();
+                        let author_handle = tweet.get("user_screen_name")?.as_str()?.to_string();
+                        let text = tweet.get("full_text")?.as_str()?.to_string();
+                        Some(Mention {
+                            id,
+                            author_handle,
+                            text,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(mentions)
+    }
+
+    pub async fn favorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        self.ensure_logged_in().await?;
+        let payload = json!({ "variables": { "tweet_id": tweet_id } });
+        self.client
+            .post(FAVORITE_TWEET_URL)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn unfavorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        self.ensure_logged_in().await?;
+        let payload = json!({ "variables": { "tweet_id": tweet_id } });
+        self.client
+            .post(UNFAVORITE_TWEET_URL)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn follow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        self.ensure_logged_in().await?;
+        self.client
+            .post(FOLLOW_URL)
+            .form(&[("user_id", user_id)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    pub async fn unfollow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        self.ensure_logged_in().await?;
+        self.client
+            .post(UNFOLLOW_URL)
+            .form(&[("user_id", user_id)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    async fn tweet_internal(
+        &self,
+        text: &str,
+        in_reply_to_id: Option<&str>,
+    ) -> Result<String, anyhow::Error> {
+        self.ensure_logged_in().await?;
+
+        let mut variables = json!({
+            "tweet_text": text,
+            "dark_request": false,
+        });
+        if let Some(in_reply_to_id) = in_reply_to_id {
+            variables["reply"] = json!({ "in_reply_to_tweet_id": in_reply_to_id });
+        }
+
+        let payload = json!({ "variables": variables });
+
+        let response = self
+            .client
+            .post(CREATE_TWEET_URL)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: serde_json::Value = response.json().await?;
+        let id = body
+            .pointer("/data/create_tweet/tweet_results/result/rest_id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Twitter response did not contain a tweet id"))?
+            .to_string();
+
+        Ok(id)
+    }
+
+    async fn ensure_logged_in(&self) -> Result<(), anyhow::Error> {
+        let payload = json!({
+            "username": self.username,
+            "password": self.password,
+        });
+
+        self.client
+            .post(LOGIN_URL)
+            .json(&payload)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
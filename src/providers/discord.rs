@@ -0,0 +1,30 @@
+use reqwest::Client;
+use serde_json::json;
+
+pub struct Discord {
+    webhook_url: String,
+    client: Client,
+}
+
+impl Discord {
+    pub fn new(webhook_url: &str) -> Self {
+        Discord {
+            webhook_url: webhook_url.to_string(),
+            client: Client::new(),
+        }
+    }
+
+    pub async fn send_channel_message(&self, message: &str) {
+        let payload = json!({ "content": message });
+
+        if let Err(e) = self
+            .client
+            .post(&self.webhook_url)
+            .json(&payload)
+            .send()
+            .await
+        {
+            eprintln!("Failed to send Discord message: {}", e);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod ai16z_twitter;
+pub mod discord;
+pub mod oauth1;
+pub mod splitter;
+pub mod twitter;
+
+/// A single incoming tweet surfaced by a mentions/timeline poll, trimmed down
+/// to the fields agents actually need to build a reply prompt.
+#[derive(Debug, Clone)]
+pub struct Mention {
+    pub id: String,
+    pub author_handle: String,
+    pub text: String,
+}
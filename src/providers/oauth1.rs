@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+
+/// Builds an OAuth 1.0a `Authorization` header for a request to `url`.
+///
+/// `token`/`token_secret` are `None` for the `oauth/request_token` leg of the
+/// PIN flow, where the caller doesn't have an access token yet. `extra_params`
+/// are the non-oauth request params (e.g. `status`, `oauth_verifier`) that
+/// must be folded into the signature base string but are not themselves part
+/// of the returned header.
+pub fn build_header(
+    consumer_key: &str,
+    consumer_secret: &str,
+    token: Option<&str>,
+    token_secret: Option<&str>,
+    method: &str,
+    url: &str,
+    extra_params: &BTreeMap<String, String>,
+) -> String {
+    let mut oauth_params = BTreeMap::new();
+    oauth_params.insert("oauth_consumer_key".to_string(), consumer_key.to_string());
+    oauth_params.insert("oauth_nonce".to_string(), generate_nonce());
+    oauth_params.insert(
+        "oauth_signature_method".to_string(),
+        "HMAC-SHA1".to_string(),
+    );
+    oauth_params.insert("oauth_timestamp".to_string(), current_timestamp());
+    oauth_params.insert("oauth_version".to_string(), "1.0".to_string());
+    if let Some(token) = token {
+        oauth_params.insert("oauth_token".to_string(), token.to_string());
+    }
+
+    let mut signing_params = oauth_params.clone();
+    signing_params.extend(extra_params.clone());
+
+    let signature = sign(consumer_secret, token_secret, method, url, &signing_params);
+    oauth_params.insert("oauth_signature".to_string(), signature);
+
+    let header_params = oauth_params
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, urlencoding::encode(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("OAuth {}", header_params)
+}
+
+fn sign(
+    consumer_secret: &str,
+    token_secret: Option<&str>,
+    method: &str,
+    url: &str,
+    params: &BTreeMap<String, String>,
+) -> String {
+    let param_string = params
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                urlencoding::encode(key),
+                urlencoding::encode(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let base_string = format!(
+        "{}&{}&{}",
+        method,
+        urlencoding::encode(url),
+        urlencoding::encode(&param_string)
+    );
+
+    let signing_key = format!(
+        "{}&{}",
+        urlencoding::encode(consumer_secret),
+        urlencoding::encode(token_secret.unwrap_or(""))
+    );
+
+    let mut mac = Hmac::<Sha1>::new_from_slice(signing_key.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(base_string.as_bytes());
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+fn generate_nonce() -> String {
+    let mut rng = rand::thread_rng();
+    (0..32)
+        .map(|_| rng.sample(rand::distributions::Alphanumeric) as char)
+        .collect()
+}
+
+fn current_timestamp() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is before the unix epoch")
+        .as_secs()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Textbook OAuth 1.0a signature from Twitter's own "Creating a signature"
+    /// documentation, with the consumer/token secrets, nonce, and timestamp
+    /// pinned to the documented values.
+    #[test]
+    fn sign_matches_twitter_documentation_example() {
+        let mut params = BTreeMap::new();
+        params.insert(
+            "status".to_string(),
+            "Hello Ladies + Gentlemen, a signed OAuth request!".to_string(),
+        );
+        params.insert("include_entities".to_string(), "true".to_string());
+        params.insert(
+            "oauth_consumer_key".to_string(),
+            "xvz1evFS4wEEPTGEFPHBog".to_string(),
+        );
+        params.insert(
+            "oauth_nonce".to_string(),
+            "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg".to_string(),
+        );
+        params.insert(
+            "oauth_signature_method".to_string(),
+            "HMAC-SHA1".to_string(),
+        );
+        params.insert("oauth_timestamp".to_string(), "1318622958".to_string());
+        params.insert(
+            "oauth_token".to_string(),
+            "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".to_string(),
+        );
+        params.insert("oauth_version".to_string(), "1.0".to_string());
+
+        let signature = sign(
+            "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+            Some("LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE"),
+            "POST",
+            "https://api.twitter.com/1/statuses/update.json",
+            &params,
+        );
+
+        assert_eq!(signature, "tnnArxj06cWHq44gCs1OSKk/jLY=");
+    }
+
+    #[test]
+    fn sign_omits_token_secret_when_absent() {
+        // The request_token leg of the PIN flow has no token secret yet; the
+        // signing key should fall back to an empty token-secret component
+        // instead of panicking or erroring.
+        let mut params = BTreeMap::new();
+        params.insert("oauth_callback".to_string(), "oob".to_string());
+
+        let signature = sign(
+            "consumersecret",
+            None,
+            "POST",
+            "https://api.twitter.com/oauth/request_token",
+            &params,
+        );
+
+        assert!(!signature.is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+/// Twitter's classic 280-character status limit.
+const TWEET_LIMIT: usize = 280;
+
+/// Budget left over for each chunk's "🧵 n/m" counter, appended after splitting.
+const COUNTER_RESERVE: usize = 12;
+
+/// Splits `text` into a sequence of tweet-sized chunks, each tagged with a
+/// "🧵 n/m" counter. Never breaks mid-word, and counts any `http(s)://` URL as
+/// 23 characters (Twitter's t.co-shortened length) regardless of its real
+/// length, matching how the API itself weighs tweet length.
+///
+/// Returns a single-element vec, unchanged, when `text` already fits.
+pub fn split_into_tweets(text: &str) -> Vec<String> {
+    if tweet_length(text) <= TWEET_LIMIT {
+        return vec![text.to_string()];
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let budget = TWEET_LIMIT.saturating_sub(COUNTER_RESERVE);
+
+    let mut chunks: Vec<Vec<&str>> = Vec::new();
+    let mut start = 0;
+
+    while start < words.len() {
+        let mut end = start;
+        let mut len = 0;
+        while end < words.len() {
+            let separator_len = if end == start { 0 } else { 1 };
+            let word_len = word_length(words[end]);
+            if len + separator_len + word_len > budget {
+                break;
+            }
+            len += separator_len + word_len;
+            end += 1;
+        }
+        // Always make progress, even if a single word is longer than `budget`.
+        let end = end.max(start + 1);
+
+        // Prefer ending the chunk on a sentence boundary over packing it as
+        // full as possible, as long as that still makes forward progress.
+        let break_at = (start + 1..=end)
+            .rev()
+            .find(|&i| ends_sentence(words[i - 1]))
+            .unwrap_or(end);
+
+        chunks.push(words[start..break_at].to_vec());
+        start = break_at;
+    }
+
+    let total = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("{} 🧵 {}/{}", chunk.join(" "), i + 1, total))
+        .collect()
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.ends_with('.') || word.ends_with('!') || word.ends_with('?')
+}
+
+fn word_length(word: &str) -> usize {
+    if word.starts_with("http://") || word.starts_with("https://") {
+        23
+    } else {
+        word.chars().count()
+    }
+}
+
+fn tweet_length(text: &str) -> usize {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let word_total: usize = words.iter().map(|word| word_length(word)).sum();
+    word_total + words.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_at_exactly_the_limit_is_returned_unchanged() {
+        let text = "a".repeat(TWEET_LIMIT);
+        let chunks = split_into_tweets(&text);
+        assert_eq!(chunks, vec![text]);
+    }
+
+    #[test]
+    fn a_word_longer_than_the_budget_still_makes_progress() {
+        let overlong_word = "a".repeat(300);
+        let text = format!("{} b", overlong_word);
+
+        let chunks = split_into_tweets(&text);
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with(&overlong_word));
+        assert!(chunks[1].starts_with('b'));
+    }
+
+    #[test]
+    fn urls_are_weighed_as_23_chars_regardless_of_real_length() {
+        // Ten URLs whose real length would blow way past the limit, but whose
+        // t.co-shortened weight (23 chars each) fits comfortably.
+        let url = "https://example.com/some/very/long/path/segment";
+        assert!(url.len() > 23);
+        let text = vec![url; 10].join(" ");
+
+        let chunks = split_into_tweets(&text);
+
+        assert_eq!(chunks, vec![text]);
+    }
+}
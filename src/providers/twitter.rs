@@ -0,0 +1,366 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use reqwest::Client;
+
+use crate::memory::MemoryStore;
+use crate::providers::oauth1;
+use crate::providers::Mention;
+
+const STATUS_UPDATE_URL: &str = "https://api.twitter.com/1.1/statuses/update.json";
+const MENTIONS_TIMELINE_URL: &str = "https://api.twitter.com/1.1/statuses/mentions_timeline.json";
+const REQUEST_TOKEN_URL: &str = "https://api.twitter.com/oauth/request_token";
+const AUTHORIZE_URL: &str = "https://api.twitter.com/oauth/authorize";
+const ACCESS_TOKEN_URL: &str = "https://api.twitter.com/oauth/access_token";
+const FAVORITE_CREATE_URL: &str = "https://api.twitter.com/1.1/favorites/create.json";
+const FAVORITE_DESTROY_URL: &str = "https://api.twitter.com/1.1/favorites/destroy.json";
+const FRIENDSHIP_CREATE_URL: &str = "https://api.twitter.com/1.1/friendships/create.json";
+const FRIENDSHIP_DESTROY_URL: &str = "https://api.twitter.com/1.1/friendships/destroy.json";
+
+pub struct Twitter {
+    consumer_key: String,
+    consumer_secret: String,
+    access_token: String,
+    access_token_secret: String,
+    client: Client,
+}
+
+impl Twitter {
+    pub fn new(
+        consumer_key: &str,
+        consumer_secret: &str,
+        access_token: &str,
+        access_token_secret: &str,
+    ) -> Self {
+        Twitter {
+            consumer_key: consumer_key.to_string(),
+            consumer_secret: consumer_secret.to_string(),
+            access_token: access_token.to_string(),
+            access_token_secret: access_token_secret.to_string(),
+            client: Client::new(),
+        }
+    }
+
+    /// Posts `text` as a new status, returning the id of the created tweet.
+    pub async fn tweet(&self, text: String) -> Result<String, anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("status".to_string(), text);
+        self.post_status(params).await
+    }
+
+    /// Posts `text` as a reply to `in_reply_to_id`, returning the id of the new tweet.
+    pub async fn tweet_reply(
+        &self,
+        text: &str,
+        in_reply_to_id: &str,
+    ) -> Result<String, anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("status".to_string(), text.to_string());
+        params.insert(
+            "in_reply_to_status_id".to_string(),
+            in_reply_to_id.to_string(),
+        );
+        params.insert(
+            "auto_populate_reply_metadata".to_string(),
+            "true".to_string(),
+        );
+        self.post_status(params).await
+    }
+
+    /// Polls the mentions timeline, optionally bounded below by `since_id` so
+    /// already-seen mentions aren't returned again.
+    pub async fn fetch_mentions(
+        &self,
+        since_id: Option<&str>,
+    ) -> Result<Vec<Mention>, anyhow::Error> {
+        let mut params = BTreeMap::new();
+        // Without this, Twitter omits `full_text` entirely and truncates `text`
+        // at ~140 chars, so replies would be built from chopped mention content.
+        params.insert("tweet_mode".to_string(), "extended".to_string());
+        if let Some(since_id) = since_id {
+            params.insert("since_id".to_string(), since_id.to_string());
+        }
+
+        let authorization = self.build_oauth_header("GET", MENTIONS_TIMELINE_URL, &params);
+
+        let mut request = self.client.get(MENTIONS_TIMELINE_URL);
+        if !params.is_empty() {
+            request = request.query(&params);
+        }
+
+        let response = request
+            .header("Authorization", authorization)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let raw: Vec<serde_json::Value> = response.json().await?;
+        let mentions = raw
+            .into_iter()
+            .filter_map(|tweet| {
+                let id = tweet.get("id_str")?.as_str()?.to_string();
+                let author_handle = tweet.get("user")?.get("screen_name")?.as_str()?.to_string();
+                let text = tweet
+                    .get("full_text")
+                    .or_else(|| tweet.get("text"))?
+                    .as_str()?
+                    .to_string();
+                Some(Mention {
+                    id,
+                    author_handle,
+                    text,
+                })
+            })
+            .collect();
+
+        Ok(mentions)
+    }
+
+    pub async fn favorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("id".to_string(), tweet_id.to_string());
+        self.post_action(FAVORITE_CREATE_URL, params).await
+    }
+
+    pub async fn unfavorite(&self, tweet_id: &str) -> Result<(), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("id".to_string(), tweet_id.to_string());
+        self.post_action(FAVORITE_DESTROY_URL, params).await
+    }
+
+    pub async fn follow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("user_id".to_string(), user_id.to_string());
+        self.post_action(FRIENDSHIP_CREATE_URL, params).await
+    }
+
+    pub async fn unfollow(&self, user_id: &str) -> Result<(), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("user_id".to_string(), user_id.to_string());
+        self.post_action(FRIENDSHIP_DESTROY_URL, params).await
+    }
+
+    async fn post_action(
+        &self,
+        url: &str,
+        params: BTreeMap<String, String>,
+    ) -> Result<(), anyhow::Error> {
+        let authorization = self.build_oauth_header("POST", url, &params);
+
+        self.client
+            .post(url)
+            .header("Authorization", authorization)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    async fn post_status(&self, params: BTreeMap<String, String>) -> Result<String, anyhow::Error> {
+        let authorization = self.build_oauth_header("POST", STATUS_UPDATE_URL, &params);
+
+        let response = self
+            .client
+            .post(STATUS_UPDATE_URL)
+            .header("Authorization", authorization)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let body: serde_json::Value = response.json().await?;
+        let id = body
+            .get("id_str")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Twitter response did not contain an id_str"))?
+            .to_string();
+
+        Ok(id)
+    }
+
+    fn build_oauth_header(
+        &self,
+        method: &str,
+        url: &str,
+        params: &BTreeMap<String, String>,
+    ) -> String {
+        oauth1::build_header(
+            &self.consumer_key,
+            &self.consumer_secret,
+            Some(&self.access_token),
+            Some(&self.access_token_secret),
+            method,
+            url,
+            params,
+        )
+    }
+
+    /// Builds a `Twitter` client from just a consumer key/secret: reuses cached
+    /// access tokens from a previous PIN authorization if present, otherwise
+    /// walks the user through the PIN flow and caches the result for next time.
+    pub async fn from_pin_auth(
+        consumer_key: &str,
+        consumer_secret: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let (access_token, access_token_secret) = match MemoryStore::load_twitter_tokens() {
+            Some(tokens) => tokens,
+            None => {
+                let tokens = TwitterAuth::new(consumer_key, consumer_secret)
+                    .authorize_via_pin()
+                    .await?;
+                MemoryStore::save_twitter_tokens(&tokens.0, &tokens.1)?;
+                tokens
+            }
+        };
+
+        Ok(Twitter::new(
+            consumer_key,
+            consumer_secret,
+            &access_token,
+            &access_token_secret,
+        ))
+    }
+}
+
+/// Walks a user through the 3-legged OAuth PIN flow so they only ever need to
+/// hand over a consumer key/secret, never raw access tokens copied from the
+/// developer portal.
+pub struct TwitterAuth {
+    consumer_key: String,
+    consumer_secret: String,
+    client: Client,
+}
+
+impl TwitterAuth {
+    pub fn new(consumer_key: &str, consumer_secret: &str) -> Self {
+        TwitterAuth {
+            consumer_key: consumer_key.to_string(),
+            consumer_secret: consumer_secret.to_string(),
+            client: Client::new(),
+        }
+    }
+
+    /// Runs the full PIN flow interactively (printing the authorize URL and
+    /// reading the PIN from stdin) and returns `(access_token, access_token_secret)`.
+    /// Callers should cache these via `MemoryStore` so the flow doesn't need to
+    /// be repeated on every run.
+    pub async fn authorize_via_pin(&self) -> Result<(String, String), anyhow::Error> {
+        let (request_token, request_token_secret) = self.request_token().await?;
+
+        println!(
+            "Open this URL, authorize the app, and enter the PIN it gives you:\n{}?oauth_token={}",
+            AUTHORIZE_URL, request_token
+        );
+        print!("PIN: ");
+        io::stdout().flush()?;
+
+        let mut pin = String::new();
+        io::stdin().read_line(&mut pin)?;
+        let pin = pin.trim();
+
+        self.access_token(&request_token, &request_token_secret, pin)
+            .await
+    }
+
+    async fn request_token(&self) -> Result<(String, String), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("oauth_callback".to_string(), "oob".to_string());
+
+        let authorization = oauth1::build_header(
+            &self.consumer_key,
+            &self.consumer_secret,
+            None,
+            None,
+            "POST",
+            REQUEST_TOKEN_URL,
+            &params,
+        );
+
+        let response = self
+            .client
+            .post(REQUEST_TOKEN_URL)
+            .header("Authorization", authorization)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let parsed = parse_form_encoded(&response);
+        let token = parsed
+            .get("oauth_token")
+            .ok_or_else(|| anyhow::anyhow!("request_token response missing oauth_token"))?
+            .clone();
+        let secret = parsed
+            .get("oauth_token_secret")
+            .ok_or_else(|| anyhow::anyhow!("request_token response missing oauth_token_secret"))?
+            .clone();
+
+        Ok((token, secret))
+    }
+
+    async fn access_token(
+        &self,
+        request_token: &str,
+        request_token_secret: &str,
+        pin: &str,
+    ) -> Result<(String, String), anyhow::Error> {
+        let mut params = BTreeMap::new();
+        params.insert("oauth_verifier".to_string(), pin.to_string());
+
+        let authorization = oauth1::build_header(
+            &self.consumer_key,
+            &self.consumer_secret,
+            Some(request_token),
+            Some(request_token_secret),
+            "POST",
+            ACCESS_TOKEN_URL,
+            &params,
+        );
+
+        let response = self
+            .client
+            .post(ACCESS_TOKEN_URL)
+            .header("Authorization", authorization)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let parsed = parse_form_encoded(&response);
+        let access_token = parsed
+            .get("oauth_token")
+            .ok_or_else(|| anyhow::anyhow!("access_token response missing oauth_token"))?
+            .clone();
+        let access_token_secret = parsed
+            .get("oauth_token_secret")
+            .ok_or_else(|| anyhow::anyhow!("access_token response missing oauth_token_secret"))?
+            .clone();
+
+        Ok((access_token, access_token_secret))
+    }
+}
+
+fn parse_form_encoded(body: &str) -> BTreeMap<String, String> {
+    body.split('&')
+        .filter_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = decode_form_value(parts.next()?);
+            let value = decode_form_value(parts.next().unwrap_or(""));
+            Some((key, value))
+        })
+        .collect()
+}
+
+/// Decodes a single `application/x-www-form-urlencoded` key or value:
+/// `+` means space, and everything else is percent-encoded.
+fn decode_form_value(raw: &str) -> String {
+    urlencoding::decode(&raw.replace('+', " "))
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| raw.to_string())
+}